@@ -0,0 +1,337 @@
+use std::{
+    sync::{mpsc, Arc, Mutex},
+    thread,
+};
+
+use evdev_rs::enums::EventCode;
+
+use crate::{
+    config::{AppData, HoldBehaviorMode, KeyBehaviorMode, ModifierBehaviorMode, MouseBehaviorMode},
+    gamepad::{self, GamepadButton, StickDeflection},
+    mouse,
+    recorder,
+    simulator::{self, ThreadControlEvent},
+    utils::key_utils,
+};
+
+/// A single selectable keyboard key or mouse button the GUI lets the user add
+/// to the simulation. Gamepad buttons are not `KeyEvent`s: they are
+/// `GamepadButton` values added through `Message::AddGamepadButton`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyEvent(pub EventCode);
+
+impl KeyEvent {
+    pub fn mouse_left() -> Self {
+        KeyEvent(EventCode::EV_KEY(evdev_rs::enums::EV_KEY::BTN_LEFT))
+    }
+
+    pub fn mouse_middle() -> Self {
+        KeyEvent(EventCode::EV_KEY(evdev_rs::enums::EV_KEY::BTN_MIDDLE))
+    }
+
+    pub fn mouse_right() -> Self {
+        KeyEvent(EventCode::EV_KEY(evdev_rs::enums::EV_KEY::BTN_RIGHT))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    AddKey(KeyEvent),
+    AddGamepadButton(GamepadButton),
+    ToggleRunning,
+    UpdateInterval(String),
+    SetInterval(u64),
+    SetIntervalAndSave(u64),
+    UpdateKeyBehaviorMode(KeyBehaviorMode),
+    UpdateHoldBehaviorMode(HoldBehaviorMode),
+    UpdateModifierBehaviorMode(ModifierBehaviorMode),
+    UpdateMouseBehaviorMode(MouseBehaviorMode),
+    UpdateGamepadStickDeflection(StickDeflection),
+    StartRecording(String),
+    StopRecording,
+    Noop,
+}
+
+/// Holds the shared state a running simulation thread reads from, plus the
+/// control channel used to reconfigure it live instead of restarting it.
+pub struct AppModel {
+    pub app_data: AppData,
+    running: Arc<Mutex<bool>>,
+    interval_ms: Arc<Mutex<u64>>,
+    selected_keys: Arc<Mutex<Vec<EventCode>>>,
+    key_behavior: Arc<Mutex<KeyBehaviorMode>>,
+    mouse_behavior: Arc<Mutex<MouseBehaviorMode>>,
+    control_txs: Vec<mpsc::Sender<ThreadControlEvent>>,
+    // Kept separate from `selected_keys`/`control_txs`: gamepad buttons share
+    // no state with the keyboard/mouse threads, so routing them through the
+    // same `AddKey`/broadcast path corrupted whichever device updated last.
+    gamepad_buttons: Arc<Mutex<Vec<EventCode>>>,
+    gamepad_tx: Option<mpsc::Sender<ThreadControlEvent>>,
+    recording: Option<Arc<Mutex<bool>>>,
+    // The recorder thread's result, polled non-blockingly (see `poll_recording`)
+    // instead of `.recv()`'d inline in `start_recording` — that would block
+    // the single-threaded GUI dispatcher until `Message::StopRecording` fires,
+    // which itself has to go through the very `update()` call that's blocked.
+    recording_rx: Option<mpsc::Receiver<crate::error::Result<recorder::RecordedMacro>>>,
+}
+
+impl Default for AppModel {
+    fn default() -> Self {
+        let app_data = AppData::default();
+        Self {
+            selected_keys: Arc::new(Mutex::new(Vec::new())),
+            key_behavior: Arc::new(Mutex::new(app_data.key_behavior)),
+            mouse_behavior: Arc::new(Mutex::new(app_data.mouse_behavior)),
+            interval_ms: Arc::new(Mutex::new(app_data.interval_ms)),
+            running: Arc::new(Mutex::new(false)),
+            control_txs: Vec::new(),
+            gamepad_buttons: Arc::new(Mutex::new(Vec::new())),
+            gamepad_tx: None,
+            recording: None,
+            recording_rx: None,
+            app_data,
+        }
+    }
+}
+
+impl AppModel {
+    // Broadcasts a control event to every running simulation thread (keys and,
+    // once started, mouse). Updates made while stopped are only reflected in
+    // `app_data`/the shared state and get picked up the next time the threads
+    // are started.
+    fn send_control_event(&self, event: ThreadControlEvent) {
+        for tx in &self.control_txs {
+            let _ = tx.send(event.clone());
+        }
+    }
+
+    // Parallel to `send_control_event`, but targeted only at the gamepad
+    // thread so keyboard/mouse updates can't bleed into its button state.
+    fn send_gamepad_event(&self, event: ThreadControlEvent) {
+        if let Some(tx) = &self.gamepad_tx {
+            let _ = tx.send(event);
+        }
+    }
+
+    fn start_simulation(&mut self) {
+        *self.running.lock().unwrap() = true;
+        self.control_txs.clear();
+
+        let (keys_tx, keys_rx) = mpsc::channel();
+        self.control_txs.push(keys_tx);
+
+        let running = Arc::clone(&self.running);
+        let interval_ms = Arc::clone(&self.interval_ms);
+        let selected_keys = Arc::clone(&self.selected_keys);
+        let key_behavior = Arc::clone(&self.key_behavior);
+        let modifier_behavior = self.app_data.modifier_behavior;
+        let hold_behavior = self.app_data.hold_behavior;
+        let recorded_macro = self.app_data.recorded_macro.clone();
+        let output_backend = self.app_data.output_backend.clone();
+
+        thread::spawn(move || {
+            if let Err(e) = simulator::simulate_keys(
+                running,
+                interval_ms,
+                selected_keys,
+                key_behavior,
+                modifier_behavior,
+                hold_behavior,
+                recorded_macro,
+                output_backend,
+                keys_rx,
+            ) {
+                log::error!("Simulation thread exited with error: {:?}", e);
+            }
+        });
+
+        let (mouse_tx, mouse_rx) = mpsc::channel();
+        self.control_txs.push(mouse_tx);
+
+        let running = Arc::clone(&self.running);
+        let interval_ms = Arc::clone(&self.interval_ms);
+        let mouse_selected_keys = Arc::new(Mutex::new(Vec::new()));
+        let mouse_behavior = Arc::clone(&self.mouse_behavior);
+        *self.mouse_behavior.lock().unwrap() = self.app_data.mouse_behavior;
+        let output_backend = self.app_data.output_backend.clone();
+
+        thread::spawn(move || {
+            if let Err(e) = mouse::simulate_mouse(
+                running,
+                interval_ms,
+                mouse_selected_keys,
+                mouse_behavior,
+                output_backend,
+                mouse_rx,
+            ) {
+                log::error!("Mouse simulation thread exited with error: {:?}", e);
+            }
+        });
+
+        let (gamepad_tx, gamepad_rx) = mpsc::channel();
+        self.gamepad_tx = Some(gamepad_tx);
+
+        let running = Arc::clone(&self.running);
+        let gamepad_buttons = Arc::clone(&self.gamepad_buttons);
+        let deflection = self.app_data.gamepad_stick_deflection;
+
+        thread::spawn(move || {
+            if let Err(e) =
+                gamepad::simulate_gamepad(running, gamepad_buttons, deflection, gamepad_rx)
+            {
+                log::error!("Gamepad simulation thread exited with error: {:?}", e);
+            }
+        });
+
+        if !self.app_data.layer_config.source_device_path.is_empty() {
+            let running = Arc::clone(&self.running);
+            let layer_config = self.app_data.layer_config.clone();
+            let output_backend = self.app_data.output_backend.clone();
+
+            thread::spawn(move || {
+                let engine = crate::layers::LayerEngine::from_config(&layer_config);
+                let mut backend = match crate::backend::create_backend(
+                    &output_backend,
+                    &Arc::new(Mutex::new(Vec::new())),
+                    &[],
+                ) {
+                    Ok(backend) => backend,
+                    Err(e) => {
+                        log::error!("Failed to create layer-engine output device: {:?}", e);
+                        return;
+                    }
+                };
+
+                if let Err(e) = crate::layers::run_layer_engine(
+                    &layer_config.source_device_path,
+                    engine,
+                    backend.as_mut(),
+                    &running,
+                ) {
+                    log::error!("Layer engine thread exited with error: {:?}", e);
+                }
+            });
+        }
+    }
+
+    fn stop_simulation(&mut self) {
+        *self.running.lock().unwrap() = false;
+        self.control_txs.clear();
+        self.gamepad_tx = None;
+    }
+
+    fn start_recording(&mut self, device_path: String) {
+        let recording = Arc::new(Mutex::new(true));
+        self.recording = Some(Arc::clone(&recording));
+
+        let (_handle, rx) = recorder::spawn_recorder(device_path, recording);
+        self.recording_rx = Some(rx);
+    }
+
+    fn stop_recording(&mut self) {
+        if let Some(recording) = self.recording.take() {
+            *recording.lock().unwrap() = false;
+        }
+    }
+
+    /// Non-blockingly checks whether the recorder thread has produced a
+    /// result, stashing it into `app_data` if so. Meant to be driven by the
+    /// GUI's event loop (e.g. a `cosmic::iced::Subscription` tick) once that
+    /// wiring lands; harmless to call when nothing is recording.
+    pub fn poll_recording(&mut self) {
+        let Some(rx) = &self.recording_rx else {
+            return;
+        };
+
+        match rx.try_recv() {
+            Ok(Ok(recorded)) => {
+                self.app_data.recorded_macro = Some(recorded);
+                self.recording_rx = None;
+            }
+            Ok(Err(e)) => {
+                log::error!("Recording failed: {:?}", e);
+                self.recording_rx = None;
+            }
+            Err(mpsc::TryRecvError::Empty) => {}
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.recording_rx = None;
+            }
+        }
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::AddKey(KeyEvent(code)) => {
+                self.selected_keys.lock().unwrap().push(code);
+                self.send_control_event(ThreadControlEvent::UpdateKeys(
+                    self.selected_keys.lock().unwrap().clone(),
+                ));
+            }
+            Message::AddGamepadButton(button) => {
+                self.gamepad_buttons.lock().unwrap().push(button.event_code());
+                self.send_gamepad_event(ThreadControlEvent::UpdateKeys(
+                    self.gamepad_buttons.lock().unwrap().clone(),
+                ));
+            }
+            Message::ToggleRunning => {
+                if *self.running.lock().unwrap() {
+                    self.stop_simulation();
+                } else {
+                    self.start_simulation();
+                }
+            }
+            Message::UpdateInterval(raw) => {
+                if let Ok(value) = raw.parse::<u64>() {
+                    self.app_data.interval_ms = value;
+                    *self.interval_ms.lock().unwrap() = value;
+                    self.send_control_event(ThreadControlEvent::UpdateInterval(value));
+                }
+            }
+            Message::SetInterval(value) | Message::SetIntervalAndSave(value) => {
+                self.app_data.interval_ms = value;
+                *self.interval_ms.lock().unwrap() = value;
+                self.send_control_event(ThreadControlEvent::UpdateInterval(value));
+            }
+            Message::UpdateKeyBehaviorMode(mode) => {
+                self.app_data.key_behavior = mode;
+                *self.key_behavior.lock().unwrap() = mode;
+                self.send_control_event(ThreadControlEvent::UpdateKeyBehavior(mode));
+            }
+            Message::UpdateHoldBehaviorMode(mode) => {
+                self.app_data.hold_behavior = mode;
+                self.send_control_event(ThreadControlEvent::UpdateHoldBehavior(mode));
+            }
+            Message::UpdateModifierBehaviorMode(mode) => {
+                self.app_data.modifier_behavior = mode;
+            }
+            Message::UpdateMouseBehaviorMode(mode) => {
+                self.app_data.mouse_behavior = mode;
+                *self.mouse_behavior.lock().unwrap() = mode;
+            }
+            Message::UpdateGamepadStickDeflection(deflection) => {
+                self.app_data.gamepad_stick_deflection = deflection;
+            }
+            Message::StartRecording(device_path) => self.start_recording(device_path),
+            Message::StopRecording => self.stop_recording(),
+            Message::Noop => {}
+        }
+    }
+
+    pub fn selected_key_labels(&self) -> Vec<String> {
+        self.selected_keys
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|code| format!("{:?}", code))
+            .collect()
+    }
+}
+
+// Translates a raw key captured by the GUI's key-capture widget into the
+// `EventCode` the simulation engine understands, mirroring
+// `simulator::initialize_simulation_keys`'s per-key lookup.
+pub fn raw_key_to_event_code(raw: &str) -> Option<EventCode> {
+    let device_key = key_utils::raw_key_to_device_keycode(raw)?;
+    let ev_key = key_utils::keycode_to_evkey(device_key)?;
+    Some(EventCode::EV_KEY(ev_key))
+}
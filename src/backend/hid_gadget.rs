@@ -0,0 +1,182 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+};
+
+use evdev_rs::enums::{EventCode, EV_KEY};
+
+use super::OutputBackend;
+use crate::error::{Result, SimulatorError};
+
+/// Maximum number of simultaneously-pressed non-modifier keys a single HID
+/// keyboard report can represent (bytes 2-7).
+const MAX_SIMULTANEOUS_KEYS: usize = 6;
+
+const MOD_LEFT_CTRL: u8 = 1 << 0;
+const MOD_LEFT_SHIFT: u8 = 1 << 1;
+const MOD_LEFT_ALT: u8 = 1 << 2;
+const MOD_LEFT_GUI: u8 = 1 << 3;
+const MOD_RIGHT_CTRL: u8 = 1 << 4;
+const MOD_RIGHT_SHIFT: u8 = 1 << 5;
+const MOD_RIGHT_ALT: u8 = 1 << 6;
+const MOD_RIGHT_GUI: u8 = 1 << 7;
+
+// Maps our EV_KEY codes to USB HID keyboard usage IDs (HID Usage Tables, Keyboard/Keypad page).
+fn ev_key_to_hid_usage(key: EV_KEY) -> Option<u8> {
+    use EV_KEY::*;
+    Some(match key {
+        KEY_A => 0x04,
+        KEY_B => 0x05,
+        KEY_C => 0x06,
+        KEY_D => 0x07,
+        KEY_E => 0x08,
+        KEY_F => 0x09,
+        KEY_G => 0x0A,
+        KEY_H => 0x0B,
+        KEY_I => 0x0C,
+        KEY_J => 0x0D,
+        KEY_K => 0x0E,
+        KEY_L => 0x0F,
+        KEY_M => 0x10,
+        KEY_N => 0x11,
+        KEY_O => 0x12,
+        KEY_P => 0x13,
+        KEY_Q => 0x14,
+        KEY_R => 0x15,
+        KEY_S => 0x16,
+        KEY_T => 0x17,
+        KEY_U => 0x18,
+        KEY_V => 0x19,
+        KEY_W => 0x1A,
+        KEY_X => 0x1B,
+        KEY_Y => 0x1C,
+        KEY_Z => 0x1D,
+        KEY_1 => 0x1E,
+        KEY_2 => 0x1F,
+        KEY_3 => 0x20,
+        KEY_4 => 0x21,
+        KEY_5 => 0x22,
+        KEY_6 => 0x23,
+        KEY_7 => 0x24,
+        KEY_8 => 0x25,
+        KEY_9 => 0x26,
+        KEY_0 => 0x27,
+        KEY_ENTER => 0x28,
+        KEY_ESC => 0x29,
+        KEY_BACKSPACE => 0x2A,
+        KEY_TAB => 0x2B,
+        KEY_SPACE => 0x2C,
+        _ => return None,
+    })
+}
+
+fn ev_key_to_modifier_bit(key: EV_KEY) -> Option<u8> {
+    use EV_KEY::*;
+    Some(match key {
+        KEY_LEFTCTRL => MOD_LEFT_CTRL,
+        KEY_LEFTSHIFT => MOD_LEFT_SHIFT,
+        KEY_LEFTALT => MOD_LEFT_ALT,
+        KEY_LEFTMETA => MOD_LEFT_GUI,
+        KEY_RIGHTCTRL => MOD_RIGHT_CTRL,
+        KEY_RIGHTSHIFT => MOD_RIGHT_SHIFT,
+        KEY_RIGHTALT => MOD_RIGHT_ALT,
+        KEY_RIGHTMETA => MOD_RIGHT_GUI,
+        _ => return None,
+    })
+}
+
+/// Writes standard USB HID keyboard reports to a `/dev/hidgN` gadget endpoint,
+/// letting the simulator inject keystrokes into a separate physical machine
+/// over USB-OTG instead of the local `uinput` subsystem.
+pub struct HidGadgetBackend {
+    endpoint: File,
+    modifiers: u8,
+    pressed_keys: Vec<u8>,
+}
+
+impl HidGadgetBackend {
+    pub fn new(gadget_path: &str) -> Result<Self> {
+        let endpoint = OpenOptions::new().write(true).open(gadget_path).map_err(|e| {
+            SimulatorError::DeviceInitialization(format!(
+                "Failed to open HID gadget endpoint {}: {:?}",
+                gadget_path, e
+            ))
+        })?;
+
+        Ok(Self {
+            endpoint,
+            modifiers: 0,
+            pressed_keys: Vec::new(),
+        })
+    }
+
+    fn write_report(&mut self) -> Result<()> {
+        let mut report = [0u8; 8];
+        report[0] = self.modifiers;
+
+        if self.pressed_keys.len() > MAX_SIMULTANEOUS_KEYS {
+            log::warn!(
+                "{} keys held at once exceeds HID rollover limit of {}; dropping oldest",
+                self.pressed_keys.len(),
+                MAX_SIMULTANEOUS_KEYS
+            );
+        }
+
+        for (slot, &usage) in self
+            .pressed_keys
+            .iter()
+            .rev()
+            .take(MAX_SIMULTANEOUS_KEYS)
+            .enumerate()
+        {
+            report[2 + slot] = usage;
+        }
+
+        self.endpoint.write_all(&report).map_err(|e| {
+            SimulatorError::KeySimulation(format!("Failed to write HID report: {:?}", e)).into()
+        })
+    }
+}
+
+impl OutputBackend for HidGadgetBackend {
+    fn enable(&mut self, _code: EventCode) -> Result<()> {
+        // HID gadget reports are stateless snapshots of the pressed set; there is
+        // no separate enable/capability step like uinput's UI_DEV_CREATE.
+        Ok(())
+    }
+
+    fn emit(&mut self, code: EventCode, value: i32) -> Result<()> {
+        let EventCode::EV_KEY(key) = code else {
+            log::debug!("HidGadgetBackend ignoring non-key event {:?}", code);
+            return Ok(());
+        };
+
+        if let Some(bit) = ev_key_to_modifier_bit(key) {
+            if value != 0 {
+                self.modifiers |= bit;
+            } else {
+                self.modifiers &= !bit;
+            }
+            return Ok(());
+        }
+
+        let Some(usage) = ev_key_to_hid_usage(key) else {
+            log::warn!("No HID usage mapping for {:?}; ignoring", key);
+            return Ok(());
+        };
+
+        if value != 0 {
+            if !self.pressed_keys.contains(&usage) {
+                self.pressed_keys.push(usage);
+            }
+        } else {
+            self.pressed_keys.retain(|&k| k != usage);
+        }
+
+        Ok(())
+    }
+
+    fn sync(&mut self) -> Result<()> {
+        self.write_report()
+    }
+}
@@ -0,0 +1,53 @@
+mod hid_gadget;
+mod uinput;
+
+pub use hid_gadget::HidGadgetBackend;
+pub use uinput::UinputBackend;
+
+use std::sync::{Arc, Mutex};
+
+use evdev_rs::enums::EventCode;
+
+use crate::{config::OutputBackendKind, error::Result};
+
+/// A destination for simulated input, abstracting over where the events actually
+/// land: the local `uinput` virtual device or a USB HID gadget endpoint wired to
+/// a separate physical machine.
+pub trait OutputBackend {
+    /// Enables a key/button on the output. Both current backends treat this as
+    /// a no-op: `HidGadgetBackend` reports are stateless snapshots with no
+    /// capability step, and `uinput` can only enable codes before device
+    /// creation, so `UinputBackend` takes its full code set through
+    /// `UinputBackend::new`/`new_with_abs_axes` instead.
+    fn enable(&mut self, code: EventCode) -> Result<()>;
+
+    /// Emits a value change (1 = pressed, 0 = released) for an already-enabled code.
+    fn emit(&mut self, code: EventCode, value: i32) -> Result<()>;
+
+    /// Flushes any buffered state change as a single report/sync.
+    fn sync(&mut self) -> Result<()>;
+}
+
+/// Builds the configured `OutputBackend`, enabling `selected_keys` plus
+/// `extra_codes` (e.g. always-on mouse buttons) on it. `UinputBackend` gets
+/// them baked in at construction; `HidGadgetBackend` has them passed through
+/// the trait's `enable`, which does nothing there since its reports need no
+/// prior setup.
+pub fn create_backend(
+    kind: &OutputBackendKind,
+    selected_keys: &Arc<Mutex<Vec<EventCode>>>,
+    extra_codes: &[EventCode],
+) -> Result<Box<dyn OutputBackend>> {
+    match kind {
+        OutputBackendKind::Uinput => {
+            Ok(Box::new(UinputBackend::new(selected_keys, extra_codes)?))
+        }
+        OutputBackendKind::HidGadget(gadget_path) => {
+            let mut backend = HidGadgetBackend::new(gadget_path)?;
+            for &code in selected_keys.lock().unwrap().iter().chain(extra_codes) {
+                backend.enable(code)?;
+            }
+            Ok(Box::new(backend))
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use std::sync::{Arc, Mutex};
+
+use evdev_rs::{
+    enums::{EventCode, EV_SYN},
+    AbsInfo, DeviceWrapper, EnableCodeData, InputEvent, TimeVal, UInputDevice, UninitDevice,
+};
+
+use super::OutputBackend;
+use crate::{
+    constants::{MAX_RETRIES, RETRY_DELAY_MS},
+    error::{Result, SimulatorError},
+};
+
+/// The original local-machine backend: a `uinput` virtual device, written
+/// through with per-event retries (`write_with_retry`).
+pub struct UinputBackend {
+    device: UInputDevice,
+}
+
+impl UinputBackend {
+    /// Creates the device with `selected_keys` plus any `extra_codes` (e.g. the
+    /// always-on mouse buttons/REL axes) enabled. `uinput` only allows enabling
+    /// event codes before `UI_DEV_CREATE`, so everything the device will ever
+    /// emit must be known up front.
+    pub fn new(
+        selected_keys: &Arc<Mutex<Vec<EventCode>>>,
+        extra_codes: &[EventCode],
+    ) -> Result<Self> {
+        Self::new_with_abs_axes(selected_keys, extra_codes, &[])
+    }
+
+    /// Like `new`, but also enables `abs_axes` (code, absinfo) pairs via
+    /// `enable_event_code`, for devices like a gamepad that need analog axes.
+    pub fn new_with_abs_axes(
+        selected_keys: &Arc<Mutex<Vec<EventCode>>>,
+        extra_codes: &[EventCode],
+        abs_axes: &[(EventCode, AbsInfo)],
+    ) -> Result<Self> {
+        let uninit = UninitDevice::new().unwrap();
+        uninit.set_name("input_simulator");
+
+        for &code in extra_codes {
+            uninit.enable(code).unwrap();
+        }
+
+        {
+            let keys = selected_keys.lock().unwrap();
+            for &key in keys.iter() {
+                uninit.enable(key).unwrap();
+            }
+        }
+
+        for &(code, absinfo) in abs_axes {
+            uninit
+                .enable_event_code(&code, Some(EnableCodeData::AbsInfo(absinfo)))
+                .unwrap();
+        }
+
+        let device = UInputDevice::create_from_device(&uninit)?;
+        Ok(Self { device })
+    }
+
+    fn write_with_retry(&self, event: &InputEvent) -> Result<()> {
+        crate::simulator::retry(
+            || {
+                self.device
+                    .write_event(event)
+                    .map_err(|e| SimulatorError::KeySimulation(format!("Failed event: {:?}", e)).into())
+            },
+            MAX_RETRIES,
+            RETRY_DELAY_MS,
+            |attempt| {
+                log::debug!("Write event attempt {} failed, retrying...", attempt);
+            },
+        )
+    }
+}
+
+impl OutputBackend for UinputBackend {
+    fn enable(&mut self, code: EventCode) -> Result<()> {
+        // `uinput` can only enable event codes before UI_DEV_CREATE, so codes not
+        // present at construction time require recreating the device entirely
+        // (see `setup_device_with_retry`'s use on key-set changes).
+        log::debug!(
+            "UinputBackend::enable is a no-op for {:?}; recreate the device to add new codes",
+            code
+        );
+        Ok(())
+    }
+
+    fn emit(&mut self, code: EventCode, value: i32) -> Result<()> {
+        let timeval = TimeVal::new(0, 0);
+        self.write_with_retry(&InputEvent::new(&timeval, &code, value))
+    }
+
+    fn sync(&mut self) -> Result<()> {
+        let timeval = TimeVal::new(0, 0);
+        self.write_with_retry(&InputEvent::new(
+            &timeval,
+            &EventCode::EV_SYN(EV_SYN::SYN_REPORT),
+            0,
+        ))
+    }
+}
@@ -0,0 +1,157 @@
+use evdev_rs::enums::EventCode;
+use serde::{Deserialize, Serialize};
+
+use crate::{gamepad::StickDeflection, recorder::RecordedMacro};
+
+/// Persisted application state: the user's selected keys/behavior plus
+/// anything that should survive a restart (e.g. a captured macro).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AppData {
+    pub selected_keys: Vec<String>,
+    pub key_behavior: KeyBehaviorMode,
+    pub hold_behavior: HoldBehaviorMode,
+    pub modifier_behavior: ModifierBehaviorMode,
+    pub mouse_behavior: MouseBehaviorMode,
+    pub interval_ms: u64,
+    pub output_backend: OutputBackendKind,
+    pub recorded_macro: Option<RecordedMacro>,
+    pub layer_config: LayerConfig,
+    pub gamepad_stick_deflection: StickDeflection,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KeyBehaviorMode {
+    Click,
+    Hold,
+    /// Replay a previously captured `RecordedMacro` instead of generating a
+    /// fixed Click/Hold pattern.
+    Replay,
+}
+
+impl Default for KeyBehaviorMode {
+    fn default() -> Self {
+        KeyBehaviorMode::Click
+    }
+}
+
+impl std::fmt::Display for KeyBehaviorMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            KeyBehaviorMode::Click => "Click",
+            KeyBehaviorMode::Hold => "Hold",
+            KeyBehaviorMode::Replay => "Replay",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HoldBehaviorMode {
+    Continuous,
+    Cycle,
+}
+
+impl Default for HoldBehaviorMode {
+    fn default() -> Self {
+        HoldBehaviorMode::Continuous
+    }
+}
+
+impl std::fmt::Display for HoldBehaviorMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            HoldBehaviorMode::Continuous => "Continuous",
+            HoldBehaviorMode::Cycle => "Cycle",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ModifierBehaviorMode {
+    Click,
+    Hold,
+}
+
+impl Default for ModifierBehaviorMode {
+    fn default() -> Self {
+        ModifierBehaviorMode::Click
+    }
+}
+
+impl std::fmt::Display for ModifierBehaviorMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            ModifierBehaviorMode::Click => "Click",
+            ModifierBehaviorMode::Hold => "Hold",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// Which `OutputBackend` a simulation thread should write through.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum OutputBackendKind {
+    /// The local `uinput` virtual device.
+    Uinput,
+    /// A USB HID gadget endpoint, e.g. `/dev/hidg0`, for driving a separate
+    /// physical machine over USB-OTG.
+    HidGadget(String),
+}
+
+impl Default for OutputBackendKind {
+    fn default() -> Self {
+        OutputBackendKind::Uinput
+    }
+}
+
+/// How the virtual pointer should move while the simulation is running. Parallel
+/// to `KeyBehaviorMode`/`HoldBehaviorMode`, selected from the same kind of
+/// dropdown built in `ui::components`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MouseBehaviorMode {
+    /// Move the pointer continuously in one direction, by `dx`/`dy` pixels per tick.
+    Directional { dx: i32, dy: i32 },
+    /// Walk the pointer around a circle of `radius_px`, advancing one step per tick.
+    Circular { radius_px: i32 },
+    /// Emit a scroll-wheel tick every interval instead of moving the pointer.
+    Scroll { delta: i32 },
+}
+
+impl Default for MouseBehaviorMode {
+    fn default() -> Self {
+        MouseBehaviorMode::Directional { dx: 0, dy: 0 }
+    }
+}
+
+impl std::fmt::Display for MouseBehaviorMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            MouseBehaviorMode::Directional { .. } => "Directional",
+            MouseBehaviorMode::Circular { .. } => "Circular",
+            MouseBehaviorMode::Scroll { .. } => "Scroll",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// One layer's remap table plus the dual-role bindings that can switch into
+/// it, as configured from the GUI and handed to `layers::LayerEngine`.
+///
+/// Stored as `Vec<(EventCode, _)>` rather than `HashMap<EventCode, _>`:
+/// `EventCode` is a tuple-variant enum, which serde_json can't serialize as a
+/// map key ("key must be a string"), and `AppData` (which embeds this) needs
+/// to round-trip through `serde_json::to_string`/`from_str`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LayerConfig {
+    pub source_device_path: String,
+    pub layers: Vec<Vec<(EventCode, EventCode)>>,
+    pub dual_role_keys: Vec<(EventCode, DualRoleKeyConfig)>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DualRoleKeyConfig {
+    pub tap: EventCode,
+    pub layer: usize,
+    pub hold_threshold_ms: u64,
+}
@@ -0,0 +1,30 @@
+//! Tunable timing/retry constants shared across the simulator and backends.
+
+/// Max attempts for a single `write_event`/backend emit before giving up.
+pub const MAX_RETRIES: u32 = 5;
+/// Delay between `write_event` retry attempts.
+pub const RETRY_DELAY_MS: u64 = 20;
+
+/// Max attempts to create the virtual device before giving up.
+pub const MAX_DEVICE_INIT_RETRIES: u32 = 3;
+/// Delay between device-creation retry attempts.
+pub const DEVICE_INIT_RETRY_DELAY_MS: u64 = 200;
+
+/// Pause before each Hold-mode pass starts pressing keys, giving userspace
+/// time to notice a newly created device; also the back-off between checks
+/// when Replay mode has no recorded macro.
+pub const SIMULATION_HOLD_DELAY_MS: u64 = 250;
+
+/// Bounds for the user-configurable click/cycle interval slider.
+pub const MIN_INTERVAL_MS: u64 = 10;
+pub const MAX_INTERVAL_MS: u64 = 5000;
+
+/// Longest gap between two recorded events that replay will actually wait
+/// out, so a macro recorded with a long pause in the middle doesn't stall
+/// replay indefinitely.
+pub const MAX_RECORDED_IDLE_MS: u64 = 2000;
+
+/// How often an idle simulation loop (e.g. the gamepad thread once its
+/// stick/buttons are set) wakes up to check for control events, instead of
+/// busy-spinning on `try_recv`.
+pub const IDLE_POLL_INTERVAL_MS: u64 = 50;
@@ -0,0 +1,30 @@
+use std::fmt;
+
+pub type Result<T> = std::result::Result<T, SimulatorError>;
+
+#[derive(Debug)]
+pub enum SimulatorError {
+    DeviceInitialization(String),
+    KeySimulation(String),
+    Io(std::io::Error),
+}
+
+impl fmt::Display for SimulatorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimulatorError::DeviceInitialization(msg) => {
+                write!(f, "device initialization failed: {}", msg)
+            }
+            SimulatorError::KeySimulation(msg) => write!(f, "key simulation failed: {}", msg),
+            SimulatorError::Io(err) => write!(f, "io error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for SimulatorError {}
+
+impl From<std::io::Error> for SimulatorError {
+    fn from(err: std::io::Error) -> Self {
+        SimulatorError::Io(err)
+    }
+}
@@ -0,0 +1,137 @@
+use std::{
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use evdev_rs::{
+    enums::{EventCode, EV_ABS, EV_KEY},
+    AbsInfo,
+};
+
+use crate::{
+    backend::{OutputBackend, UinputBackend},
+    constants::IDLE_POLL_INTERVAL_MS,
+    error::Result,
+    simulator::{write_key_events, ThreadControlEvent},
+};
+
+/// Gamepad buttons the simulator can drive, parallel to `KeyEvent::mouse_left()`
+/// and friends for the keyboard/mouse device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GamepadButton {
+    South,
+    East,
+    North,
+    West,
+    LeftShoulder,
+    RightShoulder,
+}
+
+impl GamepadButton {
+    pub fn event_code(self) -> EventCode {
+        EventCode::EV_KEY(match self {
+            GamepadButton::South => EV_KEY::BTN_SOUTH,
+            GamepadButton::East => EV_KEY::BTN_EAST,
+            GamepadButton::North => EV_KEY::BTN_NORTH,
+            GamepadButton::West => EV_KEY::BTN_WEST,
+            GamepadButton::LeftShoulder => EV_KEY::BTN_TL,
+            GamepadButton::RightShoulder => EV_KEY::BTN_TR,
+        })
+    }
+}
+
+const ALL_BUTTONS: [GamepadButton; 6] = [
+    GamepadButton::South,
+    GamepadButton::East,
+    GamepadButton::North,
+    GamepadButton::West,
+    GamepadButton::LeftShoulder,
+    GamepadButton::RightShoulder,
+];
+
+const STICK_MIN: i32 = -32768;
+const STICK_MAX: i32 = 32767;
+const STICK_FLAT: i32 = 128;
+const STICK_FUZZ: i32 = 16;
+
+fn stick_absinfo() -> AbsInfo {
+    AbsInfo {
+        value: 0,
+        minimum: STICK_MIN,
+        maximum: STICK_MAX,
+        fuzz: STICK_FUZZ,
+        flat: STICK_FLAT,
+        resolution: 0,
+    }
+}
+
+fn gamepad_abs_axes() -> Vec<(EventCode, AbsInfo)> {
+    vec![
+        (EventCode::EV_ABS(EV_ABS::ABS_X), stick_absinfo()),
+        (EventCode::EV_ABS(EV_ABS::ABS_Y), stick_absinfo()),
+        (EventCode::EV_ABS(EV_ABS::ABS_RX), stick_absinfo()),
+        (EventCode::EV_ABS(EV_ABS::ABS_RY), stick_absinfo()),
+        (EventCode::EV_ABS(EV_ABS::ABS_Z), stick_absinfo()),
+        (EventCode::EV_ABS(EV_ABS::ABS_RZ), stick_absinfo()),
+    ]
+}
+
+/// How far and in which direction the analog stick(s) should be held while the
+/// simulation runs, configured from the GUI and persisted on `AppData`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub struct StickDeflection {
+    pub x: i32,
+    pub y: i32,
+}
+
+/// Creates the virtual gamepad device: `selected_buttons` as digital `EV_KEY`
+/// inputs plus the standard set of analog `EV_ABS` axes.
+fn setup_gamepad_device(selected_buttons: &Arc<Mutex<Vec<EventCode>>>) -> Result<UinputBackend> {
+    let button_codes: Vec<EventCode> = ALL_BUTTONS.iter().map(|b| b.event_code()).collect();
+    UinputBackend::new_with_abs_axes(selected_buttons, &button_codes, &gamepad_abs_axes())
+}
+
+/// Holds the left stick at `deflection` and the configured buttons pressed
+/// until the simulation is stopped, mirroring `simulate_keys`'s hold mode.
+pub fn simulate_gamepad(
+    running: Arc<Mutex<bool>>,
+    selected_buttons: Arc<Mutex<Vec<EventCode>>>,
+    deflection: StickDeflection,
+    control_rx: mpsc::Receiver<ThreadControlEvent>,
+) -> Result<()> {
+    let mut backend = setup_gamepad_device(&selected_buttons)?;
+    let buttons = selected_buttons.lock().unwrap().clone();
+
+    write_key_events(&mut backend, &buttons, 1)?;
+    backend.emit(EventCode::EV_ABS(EV_ABS::ABS_X), deflection.x)?;
+    backend.emit(EventCode::EV_ABS(EV_ABS::ABS_Y), deflection.y)?;
+    backend.sync()?;
+
+    while *running.lock().unwrap() {
+        // Drain only the events relevant to a gamepad thread; key/hold-behavior
+        // updates are meaningless here and are ignored like in `simulate_mouse`.
+        while let Ok(event) = control_rx.try_recv() {
+            if let ThreadControlEvent::UpdateKeys(new_buttons) = event {
+                *selected_buttons.lock().unwrap() = new_buttons;
+                backend = setup_gamepad_device(&selected_buttons)?;
+                write_key_events(&mut backend, &selected_buttons.lock().unwrap().clone(), 1)?;
+                backend.emit(EventCode::EV_ABS(EV_ABS::ABS_X), deflection.x)?;
+                backend.emit(EventCode::EV_ABS(EV_ABS::ABS_Y), deflection.y)?;
+                backend.sync()?;
+            }
+        }
+
+        // Nothing left to do until the next control event or shutdown; sleep
+        // instead of spinning the CPU on try_recv like the loop above used to.
+        thread::sleep(Duration::from_millis(IDLE_POLL_INTERVAL_MS));
+    }
+
+    // Release whatever's actually held now, not the `buttons` snapshot from
+    // before the loop — `selected_buttons` may have changed since via
+    // `UpdateKeys`.
+    write_key_events(&mut backend, &selected_buttons.lock().unwrap(), 0)?;
+    backend.emit(EventCode::EV_ABS(EV_ABS::ABS_X), 0)?;
+    backend.emit(EventCode::EV_ABS(EV_ABS::ABS_Y), 0)?;
+    backend.sync()
+}
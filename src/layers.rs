@@ -0,0 +1,367 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use evdev_rs::{enums::EventCode, Device, ReadFlag};
+
+use crate::{
+    backend::OutputBackend,
+    error::{Result, SimulatorError},
+};
+
+/// One layer's remap table: physical key code -> output code emitted while
+/// that layer is active. A physical key with no entry in the active layer
+/// falls through unresolved and is not emitted.
+#[derive(Debug, Clone, Default)]
+pub struct Layer {
+    pub remap: HashMap<EventCode, EventCode>,
+}
+
+/// A key that behaves differently depending on how long it's held: tapped
+/// quickly it emits `tap`, held past `hold_threshold_ms` it momentarily
+/// switches to `layer` instead.
+#[derive(Debug, Clone, Copy)]
+pub struct DualRoleBinding {
+    pub tap: EventCode,
+    pub layer: usize,
+    pub hold_threshold_ms: u64,
+}
+
+// Per-key bookkeeping for a dual-role key currently held down. Tracks enough
+// state to distinguish "shift only" from "shift then tapped" on release, and
+// whether another key was pressed during the hold window (which must suppress
+// the tap even if the hold threshold was never reached).
+struct PendingHold {
+    pressed_at: Instant,
+    shifted: bool,
+    other_key_pressed: bool,
+}
+
+/// Organizes the selected keys into layers and resolves dual-role keys into
+/// either their tap output or a momentary layer shift.
+pub struct LayerEngine {
+    layers: Vec<Layer>,
+    dual_role_keys: HashMap<EventCode, DualRoleBinding>,
+    active_layer: usize,
+    base_layer: usize,
+    pending_holds: HashMap<EventCode, PendingHold>,
+    // The output code a still-held physical key resolved to at press time.
+    // Released through this, not by re-resolving against `active_layer` at
+    // release time, so a key pressed under a shifted layer still releases the
+    // same code it pressed even if the dual-role key unshifts the layer first
+    // — otherwise the remap lookup at release can miss and the output key is
+    // left stuck down.
+    pressed_outputs: HashMap<EventCode, EventCode>,
+}
+
+impl LayerEngine {
+    pub fn new(layers: Vec<Layer>, dual_role_keys: HashMap<EventCode, DualRoleBinding>) -> Self {
+        Self {
+            layers,
+            dual_role_keys,
+            active_layer: 0,
+            base_layer: 0,
+            pending_holds: HashMap::new(),
+            pressed_outputs: HashMap::new(),
+        }
+    }
+
+    /// Builds an engine from the GUI-configured `LayerConfig`, mirroring
+    /// `simulator::initialize_simulation_keys`'s raw-config-to-runtime-state
+    /// conversion for the key simulator.
+    pub fn from_config(config: &crate::config::LayerConfig) -> Self {
+        let layers = config
+            .layers
+            .iter()
+            .map(|remap| Layer {
+                remap: remap.iter().copied().collect(),
+            })
+            .collect();
+
+        let dual_role_keys = config
+            .dual_role_keys
+            .iter()
+            .map(|(code, binding)| {
+                (
+                    *code,
+                    DualRoleBinding {
+                        tap: binding.tap,
+                        layer: binding.layer,
+                        hold_threshold_ms: binding.hold_threshold_ms,
+                    },
+                )
+            })
+            .collect();
+
+        Self::new(layers, dual_role_keys)
+    }
+
+    fn resolve(&self, code: EventCode) -> Option<EventCode> {
+        self.layers
+            .get(self.active_layer)
+            .and_then(|layer| layer.remap.get(&code))
+            .copied()
+    }
+
+    /// Feeds a physical key-down event, returning the output events to emit.
+    pub fn key_down(&mut self, code: EventCode, now: Instant) -> Vec<(EventCode, i32)> {
+        // Any other key pressed while a dual-role key is held suppresses that
+        // key's tap, even if it's released before the hold threshold.
+        for pending in self.pending_holds.values_mut() {
+            pending.other_key_pressed = true;
+        }
+
+        if let Some(&binding) = self.dual_role_keys.get(&code) {
+            self.pending_holds.insert(
+                code,
+                PendingHold {
+                    pressed_at: now,
+                    shifted: false,
+                    other_key_pressed: false,
+                },
+            );
+            return Vec::new();
+        }
+
+        match self.resolve(code) {
+            Some(output) => {
+                self.pressed_outputs.insert(code, output);
+                vec![(output, 1)]
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// Feeds a physical key-up event, returning the output events to emit.
+    pub fn key_up(&mut self, code: EventCode, now: Instant) -> Vec<(EventCode, i32)> {
+        if let Some(binding) = self.dual_role_keys.get(&code).copied() {
+            let pending = self.pending_holds.remove(&code);
+            // A layer-shift key always resets the layer on release, whether or
+            // not taps occurred while it was the active layer modifier.
+            self.active_layer = self.base_layer;
+
+            return match pending {
+                Some(PendingHold {
+                    pressed_at,
+                    shifted: false,
+                    other_key_pressed: false,
+                }) if now.duration_since(pressed_at)
+                    < Duration::from_millis(binding.hold_threshold_ms) =>
+                {
+                    vec![(binding.tap, 1), (binding.tap, 0)]
+                }
+                _ => Vec::new(),
+            };
+        }
+
+        // Release whatever this key actually emitted at press time, not
+        // whatever it would resolve to now — the active layer may have
+        // changed underneath it while it was held.
+        match self.pressed_outputs.remove(&code) {
+            Some(output) => vec![(output, 0)],
+            None => Vec::new(),
+        }
+    }
+
+    /// Advances time, switching any dual-role key that's been held past its
+    /// threshold into a momentary layer shift.
+    pub fn tick(&mut self, now: Instant) {
+        for (&code, pending) in self.pending_holds.iter_mut() {
+            if pending.shifted {
+                continue;
+            }
+            let Some(&binding) = self.dual_role_keys.get(&code) else {
+                continue;
+            };
+            if now.duration_since(pending.pressed_at) >= Duration::from_millis(binding.hold_threshold_ms) {
+                self.active_layer = binding.layer;
+                pending.shifted = true;
+            }
+        }
+    }
+}
+
+// Opens an evdev device node for reading remapped physical input, mirroring
+// `recorder::open_source_device`.
+fn open_source_device(device_path: &str) -> Result<Device> {
+    Device::new_from_path(device_path).map_err(|e| {
+        SimulatorError::DeviceInitialization(format!(
+            "Failed to open {} for layer remapping: {:?}",
+            device_path, e
+        ))
+        .into()
+    })
+}
+
+/// Reads physical input from `device_path`, resolves it through `engine`, and
+/// emits the result through `backend`, until `running` is cleared.
+pub fn run_layer_engine(
+    device_path: &str,
+    mut engine: LayerEngine,
+    backend: &mut dyn OutputBackend,
+    running: &Arc<Mutex<bool>>,
+) -> Result<()> {
+    let device = open_source_device(device_path)?;
+
+    while *running.lock().unwrap() {
+        engine.tick(Instant::now());
+
+        match device.next_event(ReadFlag::NORMAL) {
+            Ok((_, event)) => {
+                let now = Instant::now();
+                let emitted = match event.value {
+                    1 => engine.key_down(event.event_code, now),
+                    0 => engine.key_up(event.event_code, now),
+                    _ => Vec::new(), // ignore autorepeat (value == 2)
+                };
+
+                for (code, value) in emitted {
+                    backend.emit(code, value)?;
+                }
+                backend.sync()?;
+            }
+            Err(e) if e.raw_os_error() == Some(libc::EAGAIN) => {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            Err(e) => {
+                return Err(SimulatorError::KeySimulation(format!(
+                    "Failed reading input event: {:?}",
+                    e
+                ))
+                .into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use evdev_rs::enums::EV_KEY;
+    use std::time::Duration;
+
+    fn dual_role_engine(hold_threshold_ms: u64) -> LayerEngine {
+        let caps_lock = EventCode::EV_KEY(EV_KEY::KEY_CAPSLOCK);
+        let esc = EventCode::EV_KEY(EV_KEY::KEY_ESC);
+        let layer_code = EventCode::EV_KEY(EV_KEY::KEY_A);
+        let layer_remap = EventCode::EV_KEY(EV_KEY::KEY_LEFT);
+
+        let mut remap = HashMap::new();
+        remap.insert(layer_code, layer_remap);
+        let layers = vec![Layer::default(), Layer { remap }];
+
+        let mut dual_role_keys = HashMap::new();
+        dual_role_keys.insert(
+            caps_lock,
+            DualRoleBinding {
+                tap: esc,
+                layer: 1,
+                hold_threshold_ms,
+            },
+        );
+
+        LayerEngine::new(layers, dual_role_keys)
+    }
+
+    #[test]
+    fn tap_below_threshold_emits_tap_and_nothing_else() {
+        let mut engine = dual_role_engine(200);
+        let caps_lock = EventCode::EV_KEY(EV_KEY::KEY_CAPSLOCK);
+        let esc = EventCode::EV_KEY(EV_KEY::KEY_ESC);
+        let t0 = Instant::now();
+
+        assert!(engine.key_down(caps_lock, t0).is_empty());
+        let emitted = engine.key_up(caps_lock, t0 + Duration::from_millis(50));
+
+        assert_eq!(emitted, vec![(esc, 1), (esc, 0)]);
+    }
+
+    #[test]
+    fn hold_past_threshold_shifts_layer_and_suppresses_tap() {
+        let mut engine = dual_role_engine(200);
+        let caps_lock = EventCode::EV_KEY(EV_KEY::KEY_CAPSLOCK);
+        let t0 = Instant::now();
+
+        engine.key_down(caps_lock, t0);
+        engine.tick(t0 + Duration::from_millis(250));
+        assert_eq!(engine.active_layer, 1);
+
+        // Release after the shift: no tap should be emitted, and the layer
+        // resets back to base.
+        let emitted = engine.key_up(caps_lock, t0 + Duration::from_millis(300));
+        assert!(emitted.is_empty());
+        assert_eq!(engine.active_layer, 0);
+    }
+
+    #[test]
+    fn other_key_during_hold_suppresses_tap_even_below_threshold() {
+        let mut engine = dual_role_engine(200);
+        let caps_lock = EventCode::EV_KEY(EV_KEY::KEY_CAPSLOCK);
+        let esc = EventCode::EV_KEY(EV_KEY::KEY_ESC);
+        let layer_code = EventCode::EV_KEY(EV_KEY::KEY_A);
+        let t0 = Instant::now();
+
+        engine.key_down(caps_lock, t0);
+        // Another key is pressed (and released) while the dual-role key is
+        // still held, well before the hold threshold elapses.
+        engine.key_down(layer_code, t0 + Duration::from_millis(10));
+        engine.key_up(layer_code, t0 + Duration::from_millis(20));
+
+        let emitted = engine.key_up(caps_lock, t0 + Duration::from_millis(50));
+        assert!(emitted.is_empty(), "rollover should suppress the tap: {emitted:?}");
+        assert_ne!(emitted, vec![(esc, 1), (esc, 0)]);
+    }
+
+    #[test]
+    fn held_layer_remaps_other_keys_until_release() {
+        let mut engine = dual_role_engine(200);
+        let caps_lock = EventCode::EV_KEY(EV_KEY::KEY_CAPSLOCK);
+        let layer_code = EventCode::EV_KEY(EV_KEY::KEY_A);
+        let layer_remap = EventCode::EV_KEY(EV_KEY::KEY_LEFT);
+        let t0 = Instant::now();
+
+        engine.key_down(caps_lock, t0);
+        engine.tick(t0 + Duration::from_millis(250));
+
+        let emitted = engine.key_down(layer_code, t0 + Duration::from_millis(260));
+        assert_eq!(emitted, vec![(layer_remap, 1)]);
+
+        engine.key_up(caps_lock, t0 + Duration::from_millis(300));
+        // Back on the base layer, the same physical key no longer remaps.
+        assert!(engine.key_down(layer_code, t0 + Duration::from_millis(310)).is_empty());
+    }
+
+    #[test]
+    fn key_pressed_under_shifted_layer_releases_same_code_after_layer_unshifts() {
+        // Reproduces the stuck-key bug: a key pressed while the layer is
+        // shifted must release the code it actually emitted at press time,
+        // even if the dual-role key (and therefore the active layer) is
+        // released first.
+        let mut engine = dual_role_engine(200);
+        let caps_lock = EventCode::EV_KEY(EV_KEY::KEY_CAPSLOCK);
+        let layer_code = EventCode::EV_KEY(EV_KEY::KEY_A);
+        let layer_remap = EventCode::EV_KEY(EV_KEY::KEY_LEFT);
+        let t0 = Instant::now();
+
+        engine.key_down(caps_lock, t0);
+        engine.tick(t0 + Duration::from_millis(250));
+
+        let emitted = engine.key_down(layer_code, t0 + Duration::from_millis(260));
+        assert_eq!(emitted, vec![(layer_remap, 1)]);
+
+        // Release the dual-role key first: the layer resets to base before
+        // `layer_code` itself is released.
+        engine.key_up(caps_lock, t0 + Duration::from_millis(300));
+        assert_eq!(engine.active_layer, 0);
+
+        // `layer_code` has no mapping on the base layer, so re-resolving at
+        // release time would drop this event and leave `layer_remap` stuck
+        // down; it must instead release the code it pressed.
+        let emitted = engine.key_up(layer_code, t0 + Duration::from_millis(310));
+        assert_eq!(emitted, vec![(layer_remap, 0)]);
+    }
+}
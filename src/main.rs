@@ -0,0 +1,23 @@
+mod app;
+mod backend;
+mod config;
+mod constants;
+mod error;
+mod gamepad;
+mod layers;
+mod mouse;
+mod recorder;
+mod simulator;
+mod ui;
+mod utils;
+
+use app::AppModel;
+
+fn main() {
+    env_logger::init();
+
+    // Full GUI wiring (cosmic::Application impl, view(), subscriptions) lives
+    // outside the scope of this series; this boots just enough of the model
+    // to exercise the simulation/control-channel wiring in `app::AppModel`.
+    let _model = AppModel::default();
+}
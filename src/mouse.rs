@@ -0,0 +1,94 @@
+use std::{
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use evdev_rs::enums::{EventCode, EV_REL};
+
+use crate::{
+    backend::{self, OutputBackend},
+    config::{MouseBehaviorMode, OutputBackendKind},
+    error::Result,
+    simulator::ThreadControlEvent,
+};
+
+/// Relative-motion/scroll capabilities the mouse thread's device needs,
+/// mirroring `simulator::ALWAYS_ON_MOUSE_CODES`.
+const MOUSE_CODES: [EventCode; 3] = [
+    EventCode::EV_REL(EV_REL::REL_X),
+    EventCode::EV_REL(EV_REL::REL_Y),
+    EventCode::EV_REL(EV_REL::REL_WHEEL),
+];
+
+// Emits one relative-motion tick (dx, dy) followed by a sync, through the same
+// backend emit/sync (retrying) path as key simulation uses.
+fn emit_relative_motion(backend: &mut dyn OutputBackend, dx: i32, dy: i32) -> Result<()> {
+    if dx != 0 {
+        backend.emit(EventCode::EV_REL(EV_REL::REL_X), dx)?;
+    }
+    if dy != 0 {
+        backend.emit(EventCode::EV_REL(EV_REL::REL_Y), dy)?;
+    }
+    backend.sync()
+}
+
+fn emit_scroll(backend: &mut dyn OutputBackend, delta: i32) -> Result<()> {
+    backend.emit(EventCode::EV_REL(EV_REL::REL_WHEEL), delta)?;
+    backend.sync()
+}
+
+/// Steps a circular path one tick forward, returning the (dx, dy) pointer delta
+/// for that step and the next angle in radians.
+fn next_circular_step(radius_px: i32, angle: f64) -> (i32, i32, f64) {
+    const STEP_RADIANS: f64 = std::f64::consts::PI / 16.0;
+    let next_angle = angle + STEP_RADIANS;
+    let dx = (radius_px as f64 * (next_angle.cos() - angle.cos())).round() as i32;
+    let dy = (radius_px as f64 * (next_angle.sin() - angle.sin())).round() as i32;
+    (dx, dy, next_angle)
+}
+
+/// Mirrors `simulate_keys`: runs until `running` is cleared, applying
+/// `ThreadControlEvent::UpdateInterval` and re-reading the shared
+/// `mouse_behavior` at the top of each tick so the GUI can retarget pointer
+/// automation without a restart.
+pub fn simulate_mouse(
+    running: Arc<Mutex<bool>>,
+    interval_ms: Arc<Mutex<u64>>,
+    selected_keys: Arc<Mutex<Vec<EventCode>>>,
+    mouse_behavior: Arc<Mutex<MouseBehaviorMode>>,
+    output_backend: OutputBackendKind,
+    control_rx: mpsc::Receiver<ThreadControlEvent>,
+) -> Result<()> {
+    let mut backend = backend::create_backend(&output_backend, &selected_keys, &MOUSE_CODES)?;
+
+    let mut mode = *mouse_behavior.lock().unwrap();
+    let mut angle = 0.0_f64;
+
+    while *running.lock().unwrap() {
+        while let Ok(event) = control_rx.try_recv() {
+            match event {
+                ThreadControlEvent::UpdateInterval(new_interval_ms) => {
+                    *interval_ms.lock().unwrap() = new_interval_ms;
+                }
+                _ => log::debug!("Ignoring non-mouse control event in mouse simulation thread"),
+            }
+        }
+        mode = *mouse_behavior.lock().unwrap();
+
+        match mode {
+            MouseBehaviorMode::Directional { dx, dy } => emit_relative_motion(&mut backend, dx, dy)?,
+            MouseBehaviorMode::Circular { radius_px } => {
+                let (dx, dy, next_angle) = next_circular_step(radius_px, angle);
+                angle = next_angle;
+                emit_relative_motion(&mut backend, dx, dy)?;
+            }
+            MouseBehaviorMode::Scroll { delta } => emit_scroll(&mut backend, delta)?,
+        }
+
+        let interval = *interval_ms.lock().unwrap();
+        thread::sleep(Duration::from_millis(interval));
+    }
+
+    Ok(())
+}
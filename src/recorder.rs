@@ -0,0 +1,135 @@
+use std::{
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use evdev_rs::{
+    enums::{EventCode, EV_SYN},
+    Device, ReadFlag,
+};
+
+use crate::{
+    backend::OutputBackend,
+    constants::MAX_RECORDED_IDLE_MS,
+    error::{Result, SimulatorError},
+};
+
+/// A single captured input event, relative to the previous one in the timeline.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RecordedEvent {
+    pub code: EventCode,
+    pub value: i32,
+    /// Milliseconds since the previous event in the macro, clamped to
+    /// `MAX_RECORDED_IDLE_MS` so replay can't stall on a long real-world pause.
+    pub delay_since_previous_ms: u64,
+}
+
+/// A captured timeline of events that can be stored in `AppData` and replayed.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct RecordedMacro {
+    pub events: Vec<RecordedEvent>,
+}
+
+// Opens an evdev device node for reading, independent of the UInputDevice we write through.
+fn open_source_device(device_path: &str) -> Result<Device> {
+    let device = Device::new_from_path(device_path).map_err(|e| {
+        SimulatorError::DeviceInitialization(format!(
+            "Failed to open {} for recording: {:?}",
+            device_path, e
+        ))
+    })?;
+    Ok(device)
+}
+
+/// Records input from `device_path` until `recording` is flipped to false,
+/// appending each event (with the SYN_REPORT boundaries preserved) to the timeline.
+pub fn record_events(device_path: &str, recording: Arc<Mutex<bool>>) -> Result<RecordedMacro> {
+    let device = open_source_device(device_path)?;
+    let mut timeline = Vec::new();
+    let mut last_event_at = Instant::now();
+
+    while *recording.lock().unwrap() {
+        match device.next_event(ReadFlag::NORMAL) {
+            Ok((_, event)) => {
+                let now = Instant::now();
+                let delay_ms = now
+                    .duration_since(last_event_at)
+                    .as_millis()
+                    .min(MAX_RECORDED_IDLE_MS as u128) as u64;
+                last_event_at = now;
+
+                timeline.push(RecordedEvent {
+                    code: event.event_code,
+                    value: event.value,
+                    delay_since_previous_ms: delay_ms,
+                });
+            }
+            Err(e) if e.raw_os_error() == Some(libc::EAGAIN) => {
+                thread::sleep(Duration::from_millis(1));
+            }
+            Err(e) => {
+                return Err(SimulatorError::KeySimulation(format!(
+                    "Failed reading input event: {:?}",
+                    e
+                ))
+                .into());
+            }
+        }
+    }
+
+    log::info!("Recorded {} events", timeline.len());
+    Ok(RecordedMacro { events: timeline })
+}
+
+/// Spawns a dedicated reader thread that records until `recording` is cleared,
+/// delivering the finished macro over the returned channel.
+pub fn spawn_recorder(
+    device_path: String,
+    recording: Arc<Mutex<bool>>,
+) -> (thread::JoinHandle<()>, Receiver<Result<RecordedMacro>>) {
+    let (tx, rx): (Sender<Result<RecordedMacro>>, Receiver<Result<RecordedMacro>>) =
+        mpsc::channel();
+
+    let handle = thread::spawn(move || {
+        let result = record_events(&device_path, recording);
+        let _ = tx.send(result);
+    });
+
+    (handle, rx)
+}
+
+/// Replays a previously captured macro through the virtual device, honoring the
+/// recorded inter-event delays and SYN_REPORT boundaries.
+///
+/// Recorded events are emitted one at a time via `backend.emit`, *not*
+/// `write_key_events` (which would append its own sync after every single
+/// event). A recorded `SYN_REPORT` instead triggers exactly one
+/// `backend.sync()`, so a frame captured as e.g. `[ctrl down, c down,
+/// SYN_REPORT]` replays as one batched report instead of two separately
+/// synced ones, and simultaneous key combos stay simultaneous.
+pub fn replay_macro(
+    backend: &mut dyn OutputBackend,
+    macro_: &RecordedMacro,
+    running: &Arc<Mutex<bool>>,
+) -> Result<()> {
+    for recorded in &macro_.events {
+        if !*running.lock().unwrap() {
+            break;
+        }
+
+        if recorded.delay_since_previous_ms > 0 {
+            thread::sleep(Duration::from_millis(recorded.delay_since_previous_ms));
+        }
+
+        match recorded.code {
+            EventCode::EV_SYN(EV_SYN::SYN_REPORT) => backend.sync()?,
+            code => backend.emit(code, recorded.value)?,
+        }
+    }
+
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use std::{
-    sync::{Arc, Mutex},
+    sync::{mpsc, Arc, Mutex},
     thread,
     time::Duration,
 };
@@ -8,21 +8,17 @@ use cosmic::cctk::{
     cosmic_protocols::corner_radius::v1::server::cosmic_corner_radius_toplevel_v1::Event,
     sctk::seat::keyboard::KeyCode,
 };
-use evdev_rs::{
-    enums::{EventCode, EV_KEY, EV_REL, EV_SYN},
-    DeviceWrapper, InputEvent, TimeVal, UInputDevice, UninitDevice,
-};
+use evdev_rs::enums::{EventCode, EV_KEY, EV_REL, EV_SYN};
 
 use crate::{
-    config::{HoldBehaviorMode, KeyBehaviorMode, ModifierBehaviorMode},
-    constants::{
-        DEVICE_INIT_RETRY_DELAY_MS, MAX_DEVICE_INIT_RETRIES, MAX_RETRIES, RETRY_DELAY_MS,
-        SIMULATION_HOLD_DELAY_MS,
-    },
+    backend::{self, OutputBackend},
+    config::{HoldBehaviorMode, KeyBehaviorMode, ModifierBehaviorMode, OutputBackendKind},
+    constants::{DEVICE_INIT_RETRY_DELAY_MS, MAX_DEVICE_INIT_RETRIES, SIMULATION_HOLD_DELAY_MS},
     error::{Result, SimulatorError},
+    recorder::RecordedMacro,
 };
 
-fn retry<T, F>(
+pub(crate) fn retry<T, F>(
     mut operation: F,
     max_retries: u32,
     delay_ms: u64,
@@ -47,68 +43,45 @@ where
     Err(last_error.unwrap())
 }
 
-fn write_event_with_retry(device: &UInputDevice, event: &InputEvent) -> Result<()> {
-    retry(
-        || {
-            device
-                .write_event(event)
-                .map_err(|e| SimulatorError::KeySimulation(format!("Failed event: {:?}", e)).into())
-        },
-        MAX_RETRIES,
-        RETRY_DELAY_MS,
-        |attempt| {
-            log::debug!("Write event attempt {} failed, retrying...", attempt);
-        },
-    )
-    .map_err(|e| e)
-}
-
-fn write_key_events(
-    device: &UInputDevice,
+/// Writes `keys` at `value` (1 = press, 0 = release) through `backend`, always
+/// followed by a sync so the remote end sees a consistent report/event batch.
+pub(crate) fn write_key_events(
+    backend: &mut dyn OutputBackend,
     keys: &[EventCode],
     value: i32,
-    timeval: &TimeVal,
 ) -> Result<()> {
     for &key in keys {
-        write_event_with_retry(device, &InputEvent::new(timeval, &key, value))?;
+        backend.emit(key, value)?;
     }
-    // Always sync after key events
-    write_event_with_retry(
-        device,
-        &InputEvent::new(timeval, &EventCode::EV_SYN(EV_SYN::SYN_REPORT), 0),
-    )?;
-    Ok(())
+    backend.sync()
 }
 
-// Creates and configures a virtual input device with the specified key capabilities
-fn setup_device(selected_keys: &Arc<Mutex<Vec<EventCode>>>) -> Result<UInputDevice> {
-    let device = UninitDevice::new().unwrap();
-    device.set_name("input_simulator");
-
-    {
-        let keys = selected_keys.lock().unwrap();
-
-        // Always enable mouse buttons and basic mouse functionality
-        device.enable(EventCode::EV_KEY(EV_KEY::BTN_LEFT)).unwrap();
-        device.enable(EventCode::EV_KEY(EV_KEY::BTN_RIGHT)).unwrap();
-        device
-            .enable(EventCode::EV_KEY(EV_KEY::BTN_MIDDLE))
-            .unwrap();
-        device.enable(EventCode::EV_REL(EV_REL::REL_X)).unwrap();
-        device.enable(EventCode::EV_REL(EV_REL::REL_Y)).unwrap();
-
-        for &key in keys.iter() {
-            device.enable(key).unwrap();
-        }
-    }
+/// Mouse buttons/motion that are always enabled on a fresh `UinputBackend`
+/// regardless of the user's selected keys, mirroring `KeyEvent::mouse_left()`
+/// and friends.
+const ALWAYS_ON_MOUSE_CODES: [EventCode; 5] = [
+    EventCode::EV_KEY(EV_KEY::BTN_LEFT),
+    EventCode::EV_KEY(EV_KEY::BTN_RIGHT),
+    EventCode::EV_KEY(EV_KEY::BTN_MIDDLE),
+    EventCode::EV_REL(EV_REL::REL_X),
+    EventCode::EV_REL(EV_REL::REL_Y),
+];
 
-    let uinput_device = UInputDevice::create_from_device(&device)?;
-    Ok(uinput_device)
+// Creates and configures the configured output backend with the specified key
+// capabilities in a single attempt; `setup_device_with_retry` wraps it in retries.
+fn setup_device(
+    output_backend: &OutputBackendKind,
+    selected_keys: &Arc<Mutex<Vec<EventCode>>>,
+) -> Result<Box<dyn OutputBackend>> {
+    backend::create_backend(output_backend, selected_keys, &ALWAYS_ON_MOUSE_CODES)
 }
 
-fn setup_device_with_retry(selected_keys: &Arc<Mutex<Vec<EventCode>>>) -> Result<UInputDevice> {
+fn setup_device_with_retry(
+    output_backend: &OutputBackendKind,
+    selected_keys: &Arc<Mutex<Vec<EventCode>>>,
+) -> Result<Box<dyn OutputBackend>> {
     retry(
-        || setup_device(selected_keys),
+        || setup_device(output_backend, selected_keys),
         MAX_DEVICE_INIT_RETRIES,
         DEVICE_INIT_RETRY_DELAY_MS,
         |attempt| {
@@ -127,6 +100,56 @@ fn setup_device_with_retry(selected_keys: &Arc<Mutex<Vec<EventCode>>>) -> Result
     })
 }
 
+/// A live reconfiguration request for a running `simulate_keys` thread, sent
+/// over an `mpsc` channel instead of requiring the thread to be stopped and
+/// restarted. Drained non-blockingly at the top of each loop iteration.
+#[derive(Debug, Clone)]
+pub enum ThreadControlEvent {
+    UpdateInterval(u64),
+    UpdateKeys(Vec<EventCode>),
+    UpdateKeyBehavior(KeyBehaviorMode),
+    UpdateHoldBehavior(HoldBehaviorMode),
+    Reset,
+}
+
+// Applies any pending control events to the in-flight simulation state,
+// recreating the virtual device when the key set changes since uinput can't
+// enable new codes on an already-created device.
+fn drain_control_events(
+    control_rx: &mpsc::Receiver<ThreadControlEvent>,
+    output_backend: &OutputBackendKind,
+    backend: &mut Box<dyn OutputBackend>,
+    selected_keys: &Arc<Mutex<Vec<EventCode>>>,
+    interval_ms: &Arc<Mutex<u64>>,
+    keys: &mut Vec<EventCode>,
+    mode: &mut KeyBehaviorMode,
+    hold_behavior: &mut HoldBehaviorMode,
+) -> Result<()> {
+    while let Ok(event) = control_rx.try_recv() {
+        match event {
+            ThreadControlEvent::UpdateInterval(new_interval_ms) => {
+                *interval_ms.lock().unwrap() = new_interval_ms;
+            }
+            ThreadControlEvent::UpdateKeys(new_keys) => {
+                *selected_keys.lock().unwrap() = new_keys.clone();
+                *keys = new_keys;
+                *backend = setup_device_with_retry(output_backend, selected_keys)?;
+                log::info!("Recreated virtual device for updated key set");
+            }
+            ThreadControlEvent::UpdateKeyBehavior(new_mode) => {
+                *mode = new_mode;
+            }
+            ThreadControlEvent::UpdateHoldBehavior(new_hold_behavior) => {
+                *hold_behavior = new_hold_behavior;
+            }
+            ThreadControlEvent::Reset => {
+                write_key_events(backend, keys, 0)?;
+            }
+        }
+    }
+    Ok(())
+}
+
 // Initialize simulation keys
 pub fn initialize_simulation_keys(
     app_data: &crate::config::AppData,
@@ -167,79 +190,129 @@ pub fn simulate_keys(
     key_behavior: Arc<Mutex<KeyBehaviorMode>>,
     modifier_behavior: ModifierBehaviorMode,
     hold_behavior: HoldBehaviorMode,
+    recorded_macro: Option<RecordedMacro>,
+    output_backend: OutputBackendKind,
+    control_rx: mpsc::Receiver<ThreadControlEvent>,
 ) -> Result<()> {
-    let uinput_device = setup_device_with_retry(&selected_keys)?;
-    let timeval = TimeVal::new(0, 0);
+    let mut backend = setup_device_with_retry(&output_backend, &selected_keys)?;
 
-    // Combine acquisitions for keys and mode.
-    let (keys, mode) = {
-        let keys = selected_keys.lock().unwrap().clone();
-        let mode = *key_behavior.lock().unwrap();
-        (keys, mode)
-    };
+    // Local, mutable copies that `drain_control_events` updates in place so the
+    // loop below can react to GUI changes without being stopped and restarted.
+    let mut keys = selected_keys.lock().unwrap().clone();
+    let mut mode = *key_behavior.lock().unwrap();
+    let mut hold_behavior = hold_behavior;
+    let recorded_macro = recorded_macro;
 
     log::info!("Device initialized with keys: {:?}", keys);
     log::info!("Key behavior mode set to: {:?}", mode);
     log::info!("Hold behavior mode set to: {:?}", hold_behavior);
 
     // Initial sync
-    write_event_with_retry(
-        &uinput_device,
-        &InputEvent::new(&timeval, &EventCode::EV_SYN(EV_SYN::SYN_REPORT), 0),
-    )?;
-
-    match mode {
-        KeyBehaviorMode::Hold => {
-            thread::sleep(Duration::from_millis(SIMULATION_HOLD_DELAY_MS));
-
-            // Choose behavior based on hold_behavior mode
-            match hold_behavior {
-                HoldBehaviorMode::Continuous => {
-                    // Press all keys and hold forever
-                    write_key_events(&uinput_device, &keys, 1, &timeval)?;
-
-                    while *running.lock().unwrap() {
-                        write_key_events(&uinput_device, &[], 0, &timeval)?;
-                    }
+    backend.sync()?;
 
-                    // Release keys
-                    write_key_events(&uinput_device, &keys, 0, &timeval)?;
-                }
-                HoldBehaviorMode::Cycle => {
-                    // Cycle through each key, holding for interval_ms
-                    while *running.lock().unwrap() {
+    while *running.lock().unwrap() {
+        drain_control_events(
+            &control_rx,
+            &output_backend,
+            &mut backend,
+            &selected_keys,
+            &interval_ms,
+            &mut keys,
+            &mut mode,
+            &mut hold_behavior,
+        )?;
+
+        match mode {
+            KeyBehaviorMode::Hold => {
+                thread::sleep(Duration::from_millis(SIMULATION_HOLD_DELAY_MS));
+
+                // Choose behavior based on hold_behavior mode
+                match hold_behavior {
+                    HoldBehaviorMode::Continuous => {
+                        // Press all keys and hold until the mode/key-set changes or stops.
+                        write_key_events(&mut backend, &keys, 1)?;
+                        let held_keys = keys.clone();
+
+                        while *running.lock().unwrap() {
+                            drain_control_events(
+                                &control_rx,
+                                &output_backend,
+                                &mut backend,
+                                &selected_keys,
+                                &interval_ms,
+                                &mut keys,
+                                &mut mode,
+                                &mut hold_behavior,
+                            )?;
+                            if mode != KeyBehaviorMode::Hold
+                                || hold_behavior != HoldBehaviorMode::Continuous
+                                || keys != held_keys
+                            {
+                                break;
+                            }
+                            write_key_events(&mut backend, &[], 0)?;
+                        }
+
+                        // Release keys
+                        write_key_events(&mut backend, &keys, 0)?;
+                    }
+                    HoldBehaviorMode::Cycle => {
+                        // Cycle through each key, holding for interval_ms
                         let interval = *interval_ms.lock().unwrap();
                         if keys.is_empty() {
                             thread::sleep(Duration::from_millis(interval));
-                            continue;
-                        }
-                        for key in &keys {
-                            if !*running.lock().unwrap() {
-                                break;
+                        } else {
+                            for key in keys.clone() {
+                                if !*running.lock().unwrap() {
+                                    break;
+                                }
+                                drain_control_events(
+                                    &control_rx,
+                                    &output_backend,
+                                    &mut backend,
+                                    &selected_keys,
+                                    &interval_ms,
+                                    &mut keys,
+                                    &mut mode,
+                                    &mut hold_behavior,
+                                )?;
+                                if mode != KeyBehaviorMode::Hold
+                                    || hold_behavior != HoldBehaviorMode::Cycle
+                                {
+                                    break;
+                                }
+                                let interval = *interval_ms.lock().unwrap();
+                                write_key_events(&mut backend, &[key], 1)?;
+                                thread::sleep(Duration::from_millis(interval));
+                                write_key_events(&mut backend, &[key], 0)?;
                             }
-                            write_key_events(&uinput_device, &[*key], 1, &timeval)?;
-                            thread::sleep(Duration::from_millis(interval));
-                            write_key_events(&uinput_device, &[*key], 0, &timeval)?;
                         }
                     }
                 }
             }
-        }
-        KeyBehaviorMode::Click => {
-            while *running.lock().unwrap() {
+            KeyBehaviorMode::Click => {
                 let confirm = EventCode::EV_KEY(EV_KEY::KEY_NUMERIC_0);
 
                 log::info!("Umm pressing keys: {:?}", confirm);
-                write_key_events(&uinput_device, &[confirm], 1, &timeval)?;
-                write_key_events(&uinput_device, &keys, 0, &timeval)?;
+                write_key_events(&mut backend, &[confirm], 1)?;
+                write_key_events(&mut backend, &keys, 0)?;
                 thread::sleep(Duration::from_millis(1000));
 
-                write_key_events(&uinput_device, &[confirm], 1, &timeval)?;
-                write_key_events(&uinput_device, &keys, 0, &timeval)?;
+                write_key_events(&mut backend, &[confirm], 1)?;
+                write_key_events(&mut backend, &keys, 0)?;
                 thread::sleep(Duration::from_millis(1000));
 
                 thread::sleep(Duration::from_millis(5000));
             }
+            KeyBehaviorMode::Replay => {
+                let Some(macro_) = &recorded_macro else {
+                    log::warn!("Replay mode selected but no macro was recorded");
+                    thread::sleep(Duration::from_millis(SIMULATION_HOLD_DELAY_MS));
+                    continue;
+                };
+
+                crate::recorder::replay_macro(&mut backend, macro_, &running)?;
+            }
         }
     }
 
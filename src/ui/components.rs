@@ -2,8 +2,9 @@ use std::borrow::Cow;
 
 use crate::{
     app::{KeyEvent, Message},
-    config::{AppData, HoldBehaviorMode, KeyBehaviorMode, ModifierBehaviorMode},
+    config::{AppData, HoldBehaviorMode, KeyBehaviorMode, ModifierBehaviorMode, MouseBehaviorMode},
     constants::{MAX_INTERVAL_MS, MIN_INTERVAL_MS},
+    gamepad::{GamepadButton, StickDeflection},
     utils::handle_scroll_value,
 };
 use cosmic::{
@@ -87,6 +88,89 @@ pub fn build_mouse_buttons() -> impl Into<Element<'static, Message>> {
     .padding(5)
 }
 
+pub fn build_mouse_behavior_controls(current_mode: MouseBehaviorMode) -> Column<'static, Message> {
+    const MOUSE_BEHAVIORS: [&str; 3] = ["Directional", "Circular", "Scroll"];
+    let selected_index = MOUSE_BEHAVIORS
+        .iter()
+        .position(|&mode| mode == current_mode.to_string());
+
+    let dropdown = Dropdown::new(Cow::Borrowed(&MOUSE_BEHAVIORS[..]), selected_index, |index| {
+        match index {
+            0 => Message::UpdateMouseBehaviorMode(MouseBehaviorMode::Directional { dx: 10, dy: 0 }),
+            1 => Message::UpdateMouseBehaviorMode(MouseBehaviorMode::Circular { radius_px: 50 }),
+            2 => Message::UpdateMouseBehaviorMode(MouseBehaviorMode::Scroll { delta: 1 }),
+            _ => Message::Noop,
+        }
+    });
+
+    Column::new()
+        .push(Text::new("Mouse Behavior:").width(Length::Shrink))
+        .push(dropdown)
+        .spacing(5)
+}
+
+pub fn build_gamepad_buttons() -> impl Into<Element<'static, Message>> {
+    const BUTTONS: [(&str, GamepadButton); 6] = [
+        ("South", GamepadButton::South),
+        ("East", GamepadButton::East),
+        ("North", GamepadButton::North),
+        ("West", GamepadButton::West),
+        ("L1", GamepadButton::LeftShoulder),
+        ("R1", GamepadButton::RightShoulder),
+    ];
+
+    Container::new(
+        BUTTONS
+            .iter()
+            .fold(Row::new().spacing(8), |row, &(label, button)| {
+                row.push(
+                    button::text(label)
+                        .on_press(Message::AddGamepadButton(button))
+                        .width(Length::Fixed(60.0)),
+                )
+            }),
+    )
+    .width(Length::Fill)
+    .padding(5)
+}
+
+/// Sliders for the deflection held on the left analog stick while the
+/// gamepad simulation runs; parallel to `interval_controls`'s slider/text
+/// pairing, split across the two axes.
+pub fn build_gamepad_stick_controls(deflection: StickDeflection) -> Column<'static, Message> {
+    const STICK_MIN: i32 = -32768;
+    const STICK_MAX: i32 = 32767;
+
+    let x_slider = Slider::new(
+        STICK_MIN as f64..=STICK_MAX as f64,
+        deflection.x as f64,
+        move |value| {
+            Message::UpdateGamepadStickDeflection(StickDeflection {
+                x: value as i32,
+                ..deflection
+            })
+        },
+    );
+
+    let y_slider = Slider::new(
+        STICK_MIN as f64..=STICK_MAX as f64,
+        deflection.y as f64,
+        move |value| {
+            Message::UpdateGamepadStickDeflection(StickDeflection {
+                y: value as i32,
+                ..deflection
+            })
+        },
+    );
+
+    Column::new()
+        .push(Text::new("Stick X:").width(Length::Shrink))
+        .push(x_slider)
+        .push(Text::new("Stick Y:").width(Length::Shrink))
+        .push(y_slider)
+        .spacing(5)
+}
+
 fn build_generic_dropdown<T, F>(
     choices: &'static [&'static str],
     current_mode: T,
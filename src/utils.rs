@@ -0,0 +1,65 @@
+//! Small cross-cutting helpers used by both the simulation engine and the UI.
+
+pub mod key_utils {
+    use evdev_rs::enums::EV_KEY;
+
+    /// A raw keycode as reported by the GUI's key-capture widget (the
+    /// platform/toolkit-specific representation captured from a keypress).
+    pub type DeviceKeycode = u32;
+
+    /// Maps a raw key name captured from the GUI (e.g. "KeyA") to the
+    /// toolkit's own keycode space, before it's translated to an `EV_KEY`.
+    pub fn raw_key_to_device_keycode(raw: &str) -> Option<DeviceKeycode> {
+        raw.strip_prefix("Key")
+            .and_then(|name| name.chars().next())
+            .map(|c| c as DeviceKeycode)
+    }
+
+    /// Maps a toolkit keycode to the `evdev` `EV_KEY` it corresponds to.
+    pub fn keycode_to_evkey(code: DeviceKeycode) -> Option<EV_KEY> {
+        char::from_u32(code).and_then(|c| {
+            Some(match c.to_ascii_uppercase() {
+                'A' => EV_KEY::KEY_A,
+                'B' => EV_KEY::KEY_B,
+                'C' => EV_KEY::KEY_C,
+                'D' => EV_KEY::KEY_D,
+                'E' => EV_KEY::KEY_E,
+                'F' => EV_KEY::KEY_F,
+                'G' => EV_KEY::KEY_G,
+                'H' => EV_KEY::KEY_H,
+                'I' => EV_KEY::KEY_I,
+                'J' => EV_KEY::KEY_J,
+                'K' => EV_KEY::KEY_K,
+                'L' => EV_KEY::KEY_L,
+                'M' => EV_KEY::KEY_M,
+                'N' => EV_KEY::KEY_N,
+                'O' => EV_KEY::KEY_O,
+                'P' => EV_KEY::KEY_P,
+                'Q' => EV_KEY::KEY_Q,
+                'R' => EV_KEY::KEY_R,
+                'S' => EV_KEY::KEY_S,
+                'T' => EV_KEY::KEY_T,
+                'U' => EV_KEY::KEY_U,
+                'V' => EV_KEY::KEY_V,
+                'W' => EV_KEY::KEY_W,
+                'X' => EV_KEY::KEY_X,
+                'Y' => EV_KEY::KEY_Y,
+                'Z' => EV_KEY::KEY_Z,
+                _ => return None,
+            })
+        })
+    }
+}
+
+/// Adds a scroll-wheel delta to `current` and clamps the result into
+/// `[min, max]`, used by sliders/inputs that
+/// support mouse-wheel adjustment (e.g. the interval controls).
+pub fn handle_scroll_value(current: u64, delta: cosmic::iced::mouse::ScrollDelta, min: f32, max: f32) -> u64 {
+    let amount = match delta {
+        cosmic::iced::mouse::ScrollDelta::Lines { y, .. } => y,
+        cosmic::iced::mouse::ScrollDelta::Pixels { y, .. } => y,
+    };
+
+    let next = current as f32 + amount;
+    next.clamp(min, max) as u64
+}